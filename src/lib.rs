@@ -1,17 +1,40 @@
 use std::fmt;
-use std::mem::{replace as mem_replace, take as mem_take};
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::mem::{ManuallyDrop, MaybeUninit};
+use std::ops::{Bound, RangeBounds};
+use std::ptr;
+
+pub mod spsc;
+
+/// Casts a slice of initialized `MaybeUninit<T>` to `&[T]`.
+///
+/// # Safety
+/// Every element of `slice` must be initialized.
+unsafe fn slice_assume_init_ref<T>(slice: &[MaybeUninit<T>]) -> &[T] {
+    &*(slice as *const [MaybeUninit<T>] as *const [T])
+}
+
+/// Casts a slice of initialized `MaybeUninit<T>` to `&mut [T]`.
+///
+/// # Safety
+/// Every element of `slice` must be initialized.
+unsafe fn slice_assume_init_mut<T>(slice: &mut [MaybeUninit<T>]) -> &mut [T] {
+    &mut *(slice as *mut [MaybeUninit<T>] as *mut [T])
+}
+
+fn uninit_storage<T>(cap: usize) -> Box<[MaybeUninit<T>]> {
+    (0..cap).map(|_| MaybeUninit::uninit()).collect()
+}
 
 pub struct CircularBuffer<T> {
-    buf: Vec<T>,
+    buf: Box<[MaybeUninit<T>]>,
     cap: usize,
     tail: usize,
     len: usize,
 }
 
-impl<T> std::ops::Index<usize> for CircularBuffer<T>
-where
-    T: Default + Clone,
-{
+impl<T> std::ops::Index<usize> for CircularBuffer<T> {
     type Output = T;
     fn index(&self, index: usize) -> &Self::Output {
         assert!(
@@ -20,20 +43,15 @@ where
             self.len
         );
 
-        &self.buf[(self.head() + index) % self.len]
+        unsafe { self.buf[(self.head() + index) % self.cap].assume_init_ref() }
     }
 }
 
-impl<T> CircularBuffer<T>
-where
-    T: Default + Clone,
-{
+impl<T> CircularBuffer<T> {
     pub fn new(cap: usize) -> CircularBuffer<T> {
         assert!(cap > 0_usize, "Attempt to initialize 0 size buffer");
-        let mut buf: Vec<T> = Vec::with_capacity(cap);
-        buf.resize(cap, T::default());
         CircularBuffer {
-            buf,
+            buf: uninit_storage(cap),
             tail: cap - 1_usize,
             len: 0_usize,
             cap,
@@ -45,21 +63,50 @@ where
     }
 
     pub fn insert(&mut self, item: T) {
-        self.len = std::cmp::min(self.cap, self.len + 1);
+        let _ = self.push_overwrite(item);
+    }
+
+    /// Inserts `item`, overwriting and returning the oldest (head) element once the
+    /// buffer is at capacity. Returns `None` while the buffer is still filling.
+    pub fn push_overwrite(&mut self, item: T) -> Option<T> {
+        self.tail = (self.tail + 1) % self.cap;
+        let evicted = if self.len == self.cap {
+            // Buffer is full; the slot we are about to overwrite holds the current
+            // oldest (head) element, which is moved out (not dropped) and handed
+            // back to the caller. `len` is decremented first so the slot is
+            // correctly reported as uninitialized for the `write` just below.
+            self.len -= 1;
+            Some(unsafe { self.buf[self.tail].assume_init_read() })
+        } else {
+            None
+        };
+        self.buf[self.tail].write(item);
+        self.len += 1;
+        evicted
+    }
+
+    /// Inserts `item` unless the buffer is already at capacity, in which case `item`
+    /// is handed back unchanged and the buffer is left untouched.
+    pub fn try_insert(&mut self, item: T) -> Result<(), T> {
+        if self.len == self.cap {
+            return Err(item);
+        }
         self.tail = (self.tail + 1) % self.cap;
-        let _ = mem_replace(&mut self.buf[self.tail], item);
+        self.buf[self.tail].write(item);
+        self.len += 1;
+        Ok(())
     }
 
     pub fn peek_tail(&self) -> Option<&T> {
         if self.len > 0 {
-            Some(&self.buf[self.tail])
+            Some(unsafe { self.buf[self.tail].assume_init_ref() })
         } else {
             None
         }
     }
 
     pub fn head(&self) -> usize {
-        (self.tail + self.len + 1) % self.len
+        (self.tail + self.cap - self.len + 1) % self.cap
     }
 
     pub fn peek_from_end(&self, len_from_tail: usize) -> Option<&T> {
@@ -75,16 +122,518 @@ where
             self.tail - len_from_tail
         };
 
-        Some(&self.buf[i])
+        Some(unsafe { self.buf[i].assume_init_ref() })
     }
 
     pub fn peek_head(&self) -> Option<&T> {
         if self.len > 0 {
-            Some(&self.buf[self.head()])
+            Some(unsafe { self.buf[self.head()].assume_init_ref() })
         } else {
             None
         }
     }
+
+    /// Returns the live contents as up to two contiguous slices in logical order: the
+    /// first runs from `head()` to the end of the backing storage, the second from
+    /// index 0 up to `tail`. The second slice is empty when the data doesn't wrap.
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        if self.len == 0 {
+            return (&[], &[]);
+        }
+        let head = self.head();
+        let first_len = std::cmp::min(self.len, self.cap - head);
+        let second_len = self.len - first_len;
+        unsafe {
+            (
+                slice_assume_init_ref(&self.buf[head..head + first_len]),
+                slice_assume_init_ref(&self.buf[0..second_len]),
+            )
+        }
+    }
+
+    /// Mutable counterpart to [`CircularBuffer::as_slices`].
+    pub fn as_slices_mut(&mut self) -> (&mut [T], &mut [T]) {
+        if self.len == 0 {
+            return (&mut [], &mut []);
+        }
+        let head = self.head();
+        let first_len = std::cmp::min(self.len, self.cap - head);
+        let second_len = self.len - first_len;
+        let (left, right) = self.buf.split_at_mut(head);
+        unsafe {
+            (
+                slice_assume_init_mut(&mut right[..first_len]),
+                slice_assume_init_mut(&mut left[..second_len]),
+            )
+        }
+    }
+
+    /// Rotates the backing storage in place so the live elements form a single
+    /// contiguous slice starting at index 0, and returns that slice.
+    pub fn make_contiguous(&mut self) -> &mut [T] {
+        if self.len == 0 {
+            return &mut [];
+        }
+        let head = self.head();
+        if head != 0 {
+            self.buf.rotate_left(head);
+            self.tail = self.len - 1;
+        }
+        unsafe { slice_assume_init_mut(&mut self.buf[0..self.len]) }
+    }
+
+    /// Iterate over the live contents in logical order, head to tail.
+    pub fn iter(&self) -> Iter<'_, T> {
+        let head = if self.len == 0 { 0 } else { self.head() };
+        Iter {
+            buf: &self.buf,
+            cap: self.cap,
+            head,
+            remaining: self.len,
+        }
+    }
+
+    /// Iterate over mutable references to the live contents in logical order, head to tail.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        let head = if self.len == 0 { 0 } else { self.head() };
+        IterMut {
+            ptr: self.buf.as_mut_ptr(),
+            cap: self.cap,
+            head,
+            remaining: self.len,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Drops all live elements and resets the buffer to the empty state, without
+    /// reallocating the backing storage.
+    ///
+    /// See [`drop_range`](Self::drop_range) for the panic-safety ordering this
+    /// relies on.
+    pub fn clear(&mut self) {
+        if self.len == 0 {
+            return;
+        }
+        let head = self.head();
+        let len = self.len;
+        self.len = 0;
+        self.tail = self.cap - 1;
+        self.drop_range(head, len);
+    }
+
+    /// Drops the tail-most elements beyond `new_len`, leaving the head-most `new_len`
+    /// elements in place. A no-op if `new_len >= self.len()`.
+    ///
+    /// See [`drop_range`](Self::drop_range) for the panic-safety ordering this
+    /// relies on.
+    pub fn truncate(&mut self, new_len: usize) {
+        if new_len >= self.len {
+            return;
+        }
+        let head = self.head();
+        let old_len = self.len;
+        self.len = new_len;
+        self.tail = Self::tail_for(head, new_len, self.cap);
+        self.drop_range((head + new_len) % self.cap, old_len - new_len);
+    }
+
+    /// The physical index of the last retained element, for a buffer whose `len`
+    /// live elements start at physical index `head` — the `tail` value `clear`,
+    /// `truncate`, and `drain` commit alongside `len` when shrinking the buffer.
+    fn tail_for(head: usize, len: usize, cap: usize) -> usize {
+        if len == 0 {
+            cap - 1
+        } else {
+            (head + len - 1) % cap
+        }
+    }
+
+    /// Removes and yields the elements in the given logical-index `range`, shifting
+    /// the remaining live elements to stay contiguous in logical order. Dropping the
+    /// returned `Drain` without exhausting it still removes the whole range.
+    ///
+    /// `len`/`tail` are eagerly shrunk to exclude the entire drained range (and the
+    /// not-yet-restored tail beyond `end`) before `Drain` is handed out, just as
+    /// [`CircularBuffer::truncate`] shrinks before dropping: the worst case if
+    /// `Drain` is leaked (e.g. via `mem::forget`) is a leak of the un-restored
+    /// suffix, never a double-drop on the next drop or iteration.
+    pub fn drain(&mut self, range: impl RangeBounds<usize>) -> Drain<'_, T> {
+        let len = self.len;
+        let start = match range.start_bound() {
+            Bound::Included(&s) => s,
+            Bound::Excluded(&s) => s + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&e) => e + 1,
+            Bound::Excluded(&e) => e,
+            Bound::Unbounded => len,
+        };
+        assert!(
+            start <= end && end <= len,
+            "Drain range out of bounds for buffer of length {len}"
+        );
+
+        let head = if len == 0 { 0 } else { self.head() };
+        self.len = start;
+        self.tail = Self::tail_for(head, start, self.cap);
+
+        Drain {
+            buffer: self,
+            head,
+            start,
+            end,
+            front: start,
+            back: end,
+            orig_len: len,
+        }
+    }
+
+    fn drop_live_elements(&mut self) {
+        if self.len == 0 {
+            return;
+        }
+        let head = self.head();
+        self.drop_range(head, self.len);
+    }
+
+    /// Drops the `len` live elements starting at physical index `head`, wrapping
+    /// around the end of the backing storage as needed. Callers that are shrinking
+    /// `len`/`tail` to exclude this range must commit that shrink first, so that a
+    /// panic partway through can't leave stale bookkeeping pointing at an
+    /// already-dropped slot.
+    fn drop_range(&mut self, head: usize, len: usize) {
+        let first_len = std::cmp::min(len, self.cap - head);
+        let second_len = len - first_len;
+        unsafe {
+            for i in 0..first_len {
+                ptr::drop_in_place(self.buf[head + i].as_mut_ptr());
+            }
+            for i in 0..second_len {
+                ptr::drop_in_place(self.buf[i].as_mut_ptr());
+            }
+        }
+    }
+}
+
+impl<T> Drop for CircularBuffer<T> {
+    fn drop(&mut self) {
+        self.drop_live_elements();
+    }
+}
+
+/// Draining iterator over a logical-index range of a [`CircularBuffer`], returned by
+/// [`CircularBuffer::drain`].
+pub struct Drain<'a, T> {
+    buffer: &'a mut CircularBuffer<T>,
+    /// Physical index of the buffer's logical index 0, fixed for the lifetime of
+    /// this `Drain` (the live region this was computed from is never rotated).
+    head: usize,
+    start: usize,
+    end: usize,
+    front: usize,
+    back: usize,
+    /// `buffer.len` as it was before `drain()` eagerly shrank it, needed to know
+    /// how many tail elements beyond `end` still need restoring in `Drop`.
+    orig_len: usize,
+}
+
+impl<'a, T> Iterator for Drain<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        let idx = (self.head + self.front) % self.buffer.cap;
+        self.front += 1;
+        Some(unsafe { self.buffer.buf[idx].assume_init_read() })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.back - self.front;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Drain<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        self.back -= 1;
+        let idx = (self.head + self.back) % self.buffer.cap;
+        Some(unsafe { self.buffer.buf[idx].assume_init_read() })
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Drain<'a, T> {}
+
+impl<'a, T> Drop for Drain<'a, T> {
+    fn drop(&mut self) {
+        let cap = self.buffer.cap;
+        // Drop any elements the caller never iterated to.
+        unsafe {
+            for i in self.front..self.back {
+                let idx = (self.head + i) % cap;
+                ptr::drop_in_place(self.buffer.buf[idx].as_mut_ptr());
+            }
+        }
+
+        // Close the gap by sliding the elements after `end` down to start at
+        // `start`, element by element since the range may straddle the physical
+        // wrap point.
+        let move_count = self.orig_len - self.end;
+        // Nothing was actually drained (an empty range): `start == end`, so the
+        // slide below would just copy each slot onto itself. Skip it.
+        if move_count > 0 && self.end > self.start {
+            for i in 0..move_count {
+                let src = (self.head + self.end + i) % cap;
+                let dst = (self.head + self.start + i) % cap;
+                unsafe {
+                    let val = self.buffer.buf[src].assume_init_read();
+                    self.buffer.buf[dst].write(val);
+                }
+            }
+        }
+
+        let new_len = self.start + move_count;
+        self.buffer.len = new_len;
+        self.buffer.tail = CircularBuffer::<T>::tail_for(self.head, new_len, cap);
+    }
+}
+
+/// Borrowing iterator over a [`CircularBuffer`], yielding elements head to tail.
+pub struct Iter<'a, T> {
+    buf: &'a [MaybeUninit<T>],
+    cap: usize,
+    head: usize,
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let idx = self.head;
+        self.head = (self.head + 1) % self.cap;
+        self.remaining -= 1;
+        Some(unsafe { self.buf[idx].assume_init_ref() })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let idx = (self.head + self.remaining) % self.cap;
+        Some(unsafe { self.buf[idx].assume_init_ref() })
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+/// Mutably borrowing iterator over a [`CircularBuffer`], yielding elements head to tail.
+pub struct IterMut<'a, T> {
+    ptr: *mut MaybeUninit<T>,
+    cap: usize,
+    head: usize,
+    remaining: usize,
+    _marker: PhantomData<&'a mut T>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let idx = self.head;
+        self.head = (self.head + 1) % self.cap;
+        self.remaining -= 1;
+        Some(unsafe { (*self.ptr.add(idx)).assume_init_mut() })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let idx = (self.head + self.remaining) % self.cap;
+        Some(unsafe { (*self.ptr.add(idx)).assume_init_mut() })
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterMut<'a, T> {}
+
+// SAFETY: IterMut hands out disjoint `&mut T` for each index it visits, the same
+// guarantee slice::IterMut relies on, so it is Send/Sync whenever `&mut T` is.
+unsafe impl<'a, T: Send> Send for IterMut<'a, T> {}
+unsafe impl<'a, T: Sync> Sync for IterMut<'a, T> {}
+
+/// Owning iterator over a [`CircularBuffer`], yielding elements head to tail.
+pub struct IntoIter<T> {
+    buf: Box<[MaybeUninit<T>]>,
+    cap: usize,
+    head: usize,
+    remaining: usize,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let idx = self.head;
+        self.head = (self.head + 1) % self.cap;
+        self.remaining -= 1;
+        Some(unsafe { self.buf[idx].assume_init_read() })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let idx = (self.head + self.remaining) % self.cap;
+        Some(unsafe { self.buf[idx].assume_init_read() })
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> Drop for IntoIter<T> {
+    fn drop(&mut self) {
+        if self.remaining == 0 {
+            return;
+        }
+        let first_len = std::cmp::min(self.remaining, self.cap - self.head);
+        let second_len = self.remaining - first_len;
+        unsafe {
+            for i in 0..first_len {
+                ptr::drop_in_place(self.buf[self.head + i].as_mut_ptr());
+            }
+            for i in 0..second_len {
+                ptr::drop_in_place(self.buf[i].as_mut_ptr());
+            }
+        }
+    }
+}
+
+impl<T> IntoIterator for CircularBuffer<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        let head = if self.len == 0 { 0 } else { self.head() };
+        let cap = self.cap;
+        let len = self.len;
+        // `this` is never dropped, so `self`'s own `Drop` impl (which would
+        // drop the same live elements `IntoIter` is about to take ownership
+        // of) never runs, and moving `buf` out doesn't create a double owner.
+        let this = ManuallyDrop::new(self);
+        let buf = unsafe { ptr::read(&this.buf) };
+        IntoIter {
+            buf,
+            cap,
+            head,
+            remaining: len,
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a CircularBuffer<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut CircularBuffer<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+/// Collects into a buffer sized exactly to the iterator (capacity 1 for an empty
+/// iterator), so nothing is evicted.
+/// To keep only the most recent `cap` items instead, build the buffer with
+/// [`CircularBuffer::new`] and [`Extend::extend`] into it.
+impl<T> FromIterator<T> for CircularBuffer<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let items: Vec<T> = iter.into_iter().collect();
+        let mut buffer = CircularBuffer::new(items.len().max(1));
+        for item in items {
+            buffer.insert(item);
+        }
+        buffer
+    }
+}
+
+/// Inserts every item via [`CircularBuffer::insert`], so once the buffer is at
+/// capacity the oldest elements are overwritten and only the most recent `cap`
+/// items of the combined sequence survive.
+impl<T> Extend<T> for CircularBuffer<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.insert(item);
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for CircularBuffer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (first, second) = self.as_slices();
+        f.debug_list()
+            .entries(first.iter().chain(second.iter()))
+            .finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for CircularBuffer<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for CircularBuffer<T> {}
+
+impl<T: Hash> Hash for CircularBuffer<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len.hash(state);
+        for item in self.iter() {
+            item.hash(state);
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -107,7 +656,7 @@ impl fmt::Display for QueueError {
 impl std::error::Error for QueueError {}
 
 pub struct StaticSizeQueue<T> {
-    buff: Vec<T>,
+    buff: Box<[MaybeUninit<T>]>,
     front: usize,
     size: usize,
     back: usize,
@@ -118,22 +667,17 @@ pub struct StaticSizeQueue<T> {
 /// Allocated a certain capacity upfront, then the same allocated space is used to push and pop
 /// items in the queue.
 /// No dynamic allocation of space after initial construction.
-/// Type must implement Default + Clone in order to initialize the entire space up front.
-/// Entire block is initialized with Default, but not accessible until that space is overwritten
-/// with values pushed onto the queue.
-impl<T> StaticSizeQueue<T>
-where
-    T: Default + Clone,
-{
-    /// Takes in a capacity and a type. Allocates and initializes entire allocated block.
+/// The backing storage is raw uninitialized memory, so `T` needs no `Default`/`Clone` bound;
+/// slots are only ever read once `push` has written a value into them.
+impl<T> StaticSizeQueue<T> {
+    /// Takes in a capacity and a type. Allocates the entire backing block up front,
+    /// uninitialized.
     pub fn new(cap: usize) -> StaticSizeQueue<T> {
-        let mut buff: Vec<T> = Vec::with_capacity(cap);
-        buff.resize(cap, T::default());
         let front = 0_usize;
         let back = 0_usize;
         let size = 0_usize;
         StaticSizeQueue {
-            buff,
+            buff: uninit_storage(cap),
             front, // the next item to be poped off the queue
             size,  // the number of items in the queue
             back,  // the space where the next item will be pushed
@@ -148,7 +692,7 @@ where
             return Err(QueueError::QueueEmpty);
         }
 
-        let item = mem_take(&mut self.buff[self.front]);
+        let item = unsafe { self.buff[self.front].assume_init_read() };
         self.front = (self.front + 1) % self.cap;
         self.size -= 1;
         Ok(item)
@@ -162,7 +706,7 @@ where
             return Err(QueueError::QueueFull);
         }
 
-        let _ = mem_replace(&mut self.buff[self.back], item);
+        self.buff[self.back].write(item);
         self.back = (self.back + 1) % self.cap;
         self.size += 1;
         Ok(())
@@ -179,7 +723,7 @@ where
             return None;
         }
 
-        Some(&self.buff[self.front])
+        Some(unsafe { self.buff[self.front].assume_init_ref() })
     }
 
     /// Get a mutable reference to the item at the front of the queue.
@@ -188,80 +732,345 @@ where
             return None;
         }
 
-        Some(&mut self.buff[self.front])
+        Some(unsafe { self.buff[self.front].assume_init_mut() })
     }
 
     /// Utility to see if there are items in the queue
     pub fn is_empty(&self) -> bool {
         self.size == 0
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
-    #[test]
-    fn test_head_comp_overflow() {
-        let mut buffer: CircularBuffer<i32> = CircularBuffer::new(10_usize);
-        buffer.insert(1_i32);
-        buffer.insert(1_i32);
-        buffer.insert(1_i32);
-        buffer.insert(1_i32);
-        buffer.insert(1_i32);
-        buffer.insert(1_i32);
-        buffer.insert(1_i32);
-        buffer.insert(1_i32);
-        buffer.insert(1_i32);
-        buffer.insert(1_i32);
-        buffer.insert(1_i32);
-        buffer.insert(1_i32);
-        let head = buffer.head();
+    /// Drops all live elements and resets the queue to the empty state, without
+    /// reallocating the backing storage.
+    ///
+    /// See [`drop_range`](Self::drop_range) for the panic-safety ordering this
+    /// relies on.
+    pub fn clear(&mut self) {
+        if self.size == 0 {
+            return;
+        }
+        let front = self.front;
+        let size = self.size;
+        self.front = 0;
+        self.back = 0;
+        self.size = 0;
+        self.drop_range(front, size);
+    }
 
-        // 12 items
-        // tail is index 1
-        // head should be tail + 1 when it is wrapped
-        assert_eq!(head, 2_usize);
+    /// Drops the tail-most (most recently pushed) elements beyond `new_len`. A no-op
+    /// if `new_len >= self.size()`.
+    ///
+    /// See [`drop_range`](Self::drop_range) for the panic-safety ordering this
+    /// relies on.
+    pub fn truncate(&mut self, new_len: usize) {
+        if new_len >= self.size {
+            return;
+        }
+        let old_size = self.size;
+        let front = self.front;
+        self.size = new_len;
+        self.back = (front + new_len) % self.cap;
+        self.drop_range((front + new_len) % self.cap, old_size - new_len);
     }
 
-    #[test]
-    fn test_head_comp_not_full() {
-        let mut buffer: CircularBuffer<i32> = CircularBuffer::new(10_usize);
-        buffer.insert(1_i32);
-        buffer.insert(1_i32);
-        buffer.insert(1_i32);
-        buffer.insert(1_i32);
-        buffer.insert(1_i32);
-        let head = buffer.head();
+    /// Removes and yields the elements in the given logical-index `range` (0 is the
+    /// front of the queue), shifting the remaining live elements to stay contiguous
+    /// in logical order. Dropping the returned `QueueDrain` without exhausting it
+    /// still removes the whole range.
+    ///
+    /// `size`/`back` are eagerly shrunk to exclude the entire drained range (and the
+    /// not-yet-restored tail beyond `end`) before `QueueDrain` is handed out, just as
+    /// [`StaticSizeQueue::truncate`] shrinks before dropping: the worst case if
+    /// `QueueDrain` is leaked (e.g. via `mem::forget`) is a leak of the un-restored
+    /// suffix, never a double-drop on the next drop or iteration.
+    pub fn drain(&mut self, range: impl RangeBounds<usize>) -> QueueDrain<'_, T> {
+        let len = self.size;
+        let start = match range.start_bound() {
+            Bound::Included(&s) => s,
+            Bound::Excluded(&s) => s + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&e) => e + 1,
+            Bound::Excluded(&e) => e,
+            Bound::Unbounded => len,
+        };
+        assert!(
+            start <= end && end <= len,
+            "Drain range out of bounds for queue of length {len}"
+        );
 
-        assert_eq!(head, 0_usize);
-    }
+        self.size = start;
+        self.back = (self.front + start) % self.cap;
 
-    #[test]
-    fn test_head_comp_edge() {
-        let mut buffer: CircularBuffer<i32> = CircularBuffer::new(10_usize);
-        buffer.insert(1_i32);
-        buffer.insert(1_i32);
-        buffer.insert(1_i32);
-        buffer.insert(1_i32);
-        buffer.insert(1_i32);
-        buffer.insert(1_i32);
-        buffer.insert(1_i32);
-        buffer.insert(1_i32);
-        buffer.insert(1_i32);
-        buffer.insert(1_i32);
-        buffer.insert(1_i32);
-        let head = buffer.head();
+        QueueDrain {
+            queue: self,
+            start,
+            end,
+            front: start,
+            back: end,
+            orig_len: len,
+        }
+    }
 
-        // 11 items
-        // tail is index 0
-        // head should be tail + 1 when it is wrapped
-        assert_eq!(head, 1_usize);
+    fn drop_live_elements(&mut self) {
+        if self.size == 0 {
+            return;
+        }
+        self.drop_range(self.front, self.size);
     }
 
-    #[test]
-    fn test_head_comp_edge2() {
-        let mut buffer: CircularBuffer<i32> = CircularBuffer::new(10_usize);
+    /// Drops the `len` live elements starting at physical index `front`, wrapping
+    /// around the end of the backing storage as needed. Callers that are shrinking
+    /// `size`/`back` to exclude this range must commit that shrink first, so that a
+    /// panic partway through can't leave stale bookkeeping pointing at an
+    /// already-dropped slot.
+    fn drop_range(&mut self, front: usize, len: usize) {
+        let first_len = std::cmp::min(len, self.cap - front);
+        let second_len = len - first_len;
+        unsafe {
+            for i in 0..first_len {
+                ptr::drop_in_place(self.buff[front + i].as_mut_ptr());
+            }
+            for i in 0..second_len {
+                ptr::drop_in_place(self.buff[i].as_mut_ptr());
+            }
+        }
+    }
+}
+
+impl<T> Drop for StaticSizeQueue<T> {
+    fn drop(&mut self) {
+        self.drop_live_elements();
+    }
+}
+
+impl<T> StaticSizeQueue<T> {
+    /// Shared reference iteration in front-to-back logical order, used by the
+    /// `Debug`/`PartialEq`/`Hash` impls below.
+    fn logical_iter(&self) -> impl Iterator<Item = &T> + '_ {
+        (0..self.size).map(move |i| {
+            let phys = (self.front + i) % self.cap;
+            unsafe { self.buff[phys].assume_init_ref() }
+        })
+    }
+}
+
+/// Collects into a queue sized exactly to the iterator, so `push` never fails.
+/// Extending a queue built with [`StaticSizeQueue::new`] instead keeps only the
+/// first items that fit; see the [`Extend`] impl below.
+impl<T> FromIterator<T> for StaticSizeQueue<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let items: Vec<T> = iter.into_iter().collect();
+        let mut queue = StaticSizeQueue::new(items.len());
+        for item in items {
+            queue.push(item).expect("queue sized to iterator length");
+        }
+        queue
+    }
+}
+
+/// Pushes every item via [`StaticSizeQueue::push`]. Unlike `CircularBuffer`,
+/// `StaticSizeQueue` never overwrites: once the queue is full, remaining items
+/// from `iter` are silently dropped rather than evicting older elements.
+impl<T> Extend<T> for StaticSizeQueue<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            if self.push(item).is_err() {
+                break;
+            }
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for StaticSizeQueue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.logical_iter()).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for StaticSizeQueue<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.size == other.size && self.logical_iter().eq(other.logical_iter())
+    }
+}
+
+impl<T: Eq> Eq for StaticSizeQueue<T> {}
+
+impl<T: Hash> Hash for StaticSizeQueue<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.size.hash(state);
+        for item in self.logical_iter() {
+            item.hash(state);
+        }
+    }
+}
+
+/// Draining iterator over a logical-index range of a [`StaticSizeQueue`], returned by
+/// [`StaticSizeQueue::drain`].
+pub struct QueueDrain<'a, T> {
+    queue: &'a mut StaticSizeQueue<T>,
+    start: usize,
+    end: usize,
+    front: usize,
+    back: usize,
+    /// `queue.size` as it was before `drain()` eagerly shrank it, needed to know
+    /// how many tail elements beyond `end` still need restoring in `Drop`.
+    orig_len: usize,
+}
+
+impl<'a, T> Iterator for QueueDrain<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        let phys = (self.queue.front + self.front) % self.queue.cap;
+        self.front += 1;
+        Some(unsafe { self.queue.buff[phys].assume_init_read() })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.back - self.front;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for QueueDrain<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        self.back -= 1;
+        let phys = (self.queue.front + self.back) % self.queue.cap;
+        Some(unsafe { self.queue.buff[phys].assume_init_read() })
+    }
+}
+
+impl<'a, T> ExactSizeIterator for QueueDrain<'a, T> {}
+
+impl<'a, T> Drop for QueueDrain<'a, T> {
+    fn drop(&mut self) {
+        // Drop any elements the caller never iterated to.
+        unsafe {
+            for i in self.front..self.back {
+                let phys = (self.queue.front + i) % self.queue.cap;
+                ptr::drop_in_place(self.queue.buff[phys].as_mut_ptr());
+            }
+        }
+
+        // Close the gap by sliding the elements after `end` down to start at `start`,
+        // element by element since the range may straddle the physical wrap point.
+        let move_count = self.orig_len - self.end;
+        // Nothing was actually drained (an empty range): `start == end`, so the
+        // slide below would just copy each slot onto itself. Skip it.
+        if move_count > 0 && self.end > self.start {
+            for i in 0..move_count {
+                let src = (self.queue.front + self.end + i) % self.queue.cap;
+                let dst = (self.queue.front + self.start + i) % self.queue.cap;
+                unsafe {
+                    let val = self.queue.buff[src].assume_init_read();
+                    self.queue.buff[dst].write(val);
+                }
+            }
+        }
+
+        let new_len = self.start + move_count;
+        self.queue.size = new_len;
+        self.queue.back = (self.queue.front + new_len) % self.queue.cap;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// Records `self.0` to the shared log when dropped, for tests that assert on
+    /// drop order/count without caring whether the drop itself panics.
+    struct Tracker(i32, Rc<RefCell<Vec<i32>>>);
+    impl Drop for Tracker {
+        fn drop(&mut self) {
+            self.1.borrow_mut().push(self.0);
+        }
+    }
+
+    /// Like [`Tracker`], but panics partway through a drop of several elements
+    /// (on the element tagged `2`), for tests asserting that a panicking `Drop`
+    /// can't cause a double-free.
+    struct PanicOnDrop(i32, Rc<RefCell<Vec<i32>>>);
+    impl Drop for PanicOnDrop {
+        fn drop(&mut self) {
+            self.1.borrow_mut().push(self.0);
+            if self.0 == 2 {
+                panic!("PanicOnDrop::drop");
+            }
+        }
+    }
+
+    #[test]
+    fn test_head_comp_overflow() {
+        let mut buffer: CircularBuffer<i32> = CircularBuffer::new(10_usize);
+        buffer.insert(1_i32);
+        buffer.insert(1_i32);
+        buffer.insert(1_i32);
+        buffer.insert(1_i32);
+        buffer.insert(1_i32);
+        buffer.insert(1_i32);
+        buffer.insert(1_i32);
+        buffer.insert(1_i32);
+        buffer.insert(1_i32);
+        buffer.insert(1_i32);
+        buffer.insert(1_i32);
+        buffer.insert(1_i32);
+        let head = buffer.head();
+
+        // 12 items
+        // tail is index 1
+        // head should be tail + 1 when it is wrapped
+        assert_eq!(head, 2_usize);
+    }
+
+    #[test]
+    fn test_head_comp_not_full() {
+        let mut buffer: CircularBuffer<i32> = CircularBuffer::new(10_usize);
+        buffer.insert(1_i32);
+        buffer.insert(1_i32);
+        buffer.insert(1_i32);
+        buffer.insert(1_i32);
+        buffer.insert(1_i32);
+        let head = buffer.head();
+
+        assert_eq!(head, 0_usize);
+    }
+
+    #[test]
+    fn test_head_comp_edge() {
+        let mut buffer: CircularBuffer<i32> = CircularBuffer::new(10_usize);
+        buffer.insert(1_i32);
+        buffer.insert(1_i32);
+        buffer.insert(1_i32);
+        buffer.insert(1_i32);
+        buffer.insert(1_i32);
+        buffer.insert(1_i32);
+        buffer.insert(1_i32);
+        buffer.insert(1_i32);
+        buffer.insert(1_i32);
+        buffer.insert(1_i32);
+        buffer.insert(1_i32);
+        let head = buffer.head();
+
+        // 11 items
+        // tail is index 0
+        // head should be tail + 1 when it is wrapped
+        assert_eq!(head, 1_usize);
+    }
+
+    #[test]
+    fn test_head_comp_edge2() {
+        let mut buffer: CircularBuffer<i32> = CircularBuffer::new(10_usize);
         buffer.insert(1_i32);
         buffer.insert(1_i32);
         buffer.insert(1_i32);
@@ -341,6 +1150,490 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_iter_not_full() {
+        let mut buffer: CircularBuffer<i32> = CircularBuffer::new(5_usize);
+        buffer.insert(1_i32);
+        buffer.insert(2_i32);
+        buffer.insert(3_i32);
+
+        let collected: Vec<i32> = buffer.iter().copied().collect();
+        assert_eq!(collected, vec![1_i32, 2_i32, 3_i32]);
+    }
+
+    #[test]
+    fn test_iter_wrapped() {
+        let mut buffer: CircularBuffer<i32> = CircularBuffer::new(5_usize);
+        for i in 1..=8_i32 {
+            buffer.insert(i);
+        }
+
+        // cap 5, 8 inserts: live window is [4, 5, 6, 7, 8]
+        let collected: Vec<i32> = buffer.iter().copied().collect();
+        assert_eq!(collected, vec![4_i32, 5_i32, 6_i32, 7_i32, 8_i32]);
+    }
+
+    #[test]
+    fn test_iter_rev() {
+        let mut buffer: CircularBuffer<i32> = CircularBuffer::new(5_usize);
+        for i in 1..=8_i32 {
+            buffer.insert(i);
+        }
+
+        let collected: Vec<i32> = buffer.iter().rev().copied().collect();
+        assert_eq!(collected, vec![8_i32, 7_i32, 6_i32, 5_i32, 4_i32]);
+    }
+
+    #[test]
+    fn test_iter_mut() {
+        let mut buffer: CircularBuffer<i32> = CircularBuffer::new(5_usize);
+        for i in 1..=8_i32 {
+            buffer.insert(i);
+        }
+
+        for item in buffer.iter_mut() {
+            *item *= 10_i32;
+        }
+
+        let collected: Vec<i32> = buffer.iter().copied().collect();
+        assert_eq!(collected, vec![40_i32, 50_i32, 60_i32, 70_i32, 80_i32]);
+    }
+
+    #[test]
+    fn test_into_iter() {
+        let mut buffer: CircularBuffer<i32> = CircularBuffer::new(5_usize);
+        for i in 1..=8_i32 {
+            buffer.insert(i);
+        }
+
+        let collected: Vec<i32> = buffer.into_iter().collect();
+        assert_eq!(collected, vec![4_i32, 5_i32, 6_i32, 7_i32, 8_i32]);
+    }
+
+    #[test]
+    fn test_for_loop_over_ref() {
+        let mut buffer: CircularBuffer<i32> = CircularBuffer::new(5_usize);
+        buffer.insert(1_i32);
+        buffer.insert(2_i32);
+
+        let mut sum = 0_i32;
+        for item in &buffer {
+            sum += item;
+        }
+        assert_eq!(sum, 3_i32);
+    }
+
+    #[test]
+    fn test_as_slices_not_wrapped() {
+        let mut buffer: CircularBuffer<i32> = CircularBuffer::new(5_usize);
+        buffer.insert(1_i32);
+        buffer.insert(2_i32);
+        buffer.insert(3_i32);
+
+        let (first, second) = buffer.as_slices();
+        assert_eq!(first, &[1_i32, 2_i32, 3_i32]);
+        assert!(second.is_empty());
+    }
+
+    #[test]
+    fn test_as_slices_wrapped() {
+        let mut buffer: CircularBuffer<i32> = CircularBuffer::new(5_usize);
+        for i in 1..=8_i32 {
+            buffer.insert(i);
+        }
+
+        // cap 5, 8 inserts: live window is [4, 5, 6, 7, 8], split at the physical wrap
+        let (first, second) = buffer.as_slices();
+        assert_eq!(first, &[4_i32, 5_i32]);
+        assert_eq!(second, &[6_i32, 7_i32, 8_i32]);
+    }
+
+    #[test]
+    fn test_as_slices_mut() {
+        let mut buffer: CircularBuffer<i32> = CircularBuffer::new(5_usize);
+        for i in 1..=8_i32 {
+            buffer.insert(i);
+        }
+
+        {
+            let (first, second) = buffer.as_slices_mut();
+            for item in first.iter_mut().chain(second.iter_mut()) {
+                *item *= 10_i32;
+            }
+        }
+
+        let collected: Vec<i32> = buffer.iter().copied().collect();
+        assert_eq!(collected, vec![40_i32, 50_i32, 60_i32, 70_i32, 80_i32]);
+    }
+
+    #[test]
+    fn test_make_contiguous() {
+        let mut buffer: CircularBuffer<i32> = CircularBuffer::new(5_usize);
+        for i in 1..=8_i32 {
+            buffer.insert(i);
+        }
+
+        let slice = buffer.make_contiguous();
+        assert_eq!(slice, &[4_i32, 5_i32, 6_i32, 7_i32, 8_i32]);
+
+        let (first, second) = buffer.as_slices();
+        assert_eq!(first, &[4_i32, 5_i32, 6_i32, 7_i32, 8_i32]);
+        assert!(second.is_empty());
+    }
+
+    #[test]
+    fn test_no_default_bound_required() {
+        // `NoDefault` implements neither `Default` nor `Clone`, so this only
+        // compiles without those bounds; it also needs `Drop` to run correctly
+        // on eviction.
+        struct NoDefault(String);
+
+        let mut buffer: CircularBuffer<NoDefault> = CircularBuffer::new(2_usize);
+        buffer.insert(NoDefault("a".to_string()));
+        buffer.insert(NoDefault("b".to_string()));
+        buffer.insert(NoDefault("c".to_string()));
+
+        assert_eq!(buffer.peek_head().unwrap().0, "b");
+        assert_eq!(buffer.peek_tail().unwrap().0, "c");
+    }
+
+    #[test]
+    fn test_push_overwrite() {
+        let mut buffer: CircularBuffer<i32> = CircularBuffer::new(3_usize);
+        assert_eq!(buffer.push_overwrite(1_i32), None);
+        assert_eq!(buffer.push_overwrite(2_i32), None);
+        assert_eq!(buffer.push_overwrite(3_i32), None);
+        assert_eq!(buffer.push_overwrite(4_i32), Some(1_i32));
+        assert_eq!(buffer.push_overwrite(5_i32), Some(2_i32));
+
+        let collected: Vec<i32> = buffer.iter().copied().collect();
+        assert_eq!(collected, vec![3_i32, 4_i32, 5_i32]);
+    }
+
+    #[test]
+    fn test_try_insert() {
+        let mut buffer: CircularBuffer<i32> = CircularBuffer::new(3_usize);
+        assert_eq!(buffer.try_insert(1_i32), Ok(()));
+        assert_eq!(buffer.try_insert(2_i32), Ok(()));
+        assert_eq!(buffer.try_insert(3_i32), Ok(()));
+        assert_eq!(buffer.try_insert(4_i32), Err(4_i32));
+
+        let collected: Vec<i32> = buffer.iter().copied().collect();
+        assert_eq!(collected, vec![1_i32, 2_i32, 3_i32]);
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut buffer: CircularBuffer<i32> = CircularBuffer::new(5_usize);
+        for i in 1..=8_i32 {
+            buffer.insert(i);
+        }
+        buffer.clear();
+        assert_eq!(buffer.len(), 0_usize);
+        assert_eq!(buffer.peek_head(), None);
+
+        buffer.insert(100_i32);
+        buffer.insert(101_i32);
+        let collected: Vec<i32> = buffer.iter().copied().collect();
+        assert_eq!(collected, vec![100_i32, 101_i32]);
+    }
+
+    #[test]
+    fn test_truncate_not_wrapped() {
+        let mut buffer: CircularBuffer<i32> = CircularBuffer::new(5_usize);
+        buffer.insert(1_i32);
+        buffer.insert(2_i32);
+        buffer.insert(3_i32);
+        buffer.truncate(2_usize);
+
+        let collected: Vec<i32> = buffer.iter().copied().collect();
+        assert_eq!(collected, vec![1_i32, 2_i32]);
+    }
+
+    #[test]
+    fn test_truncate_wrapped() {
+        let mut buffer: CircularBuffer<i32> = CircularBuffer::new(5_usize);
+        for i in 1..=8_i32 {
+            buffer.insert(i);
+        }
+        // live window is [4, 5, 6, 7, 8]; truncate to the 3 head-most elements
+        buffer.truncate(3_usize);
+
+        let collected: Vec<i32> = buffer.iter().copied().collect();
+        assert_eq!(collected, vec![4_i32, 5_i32, 6_i32]);
+
+        // the buffer should keep working correctly afterward
+        buffer.insert(9_i32);
+        buffer.insert(10_i32);
+        let collected: Vec<i32> = buffer.iter().copied().collect();
+        assert_eq!(collected, vec![4_i32, 5_i32, 6_i32, 9_i32, 10_i32]);
+    }
+
+    #[test]
+    fn test_index_after_truncate_wrapped() {
+        let mut buffer: CircularBuffer<i32> = CircularBuffer::new(5_usize);
+        for i in 1..=6_i32 {
+            buffer.insert(i);
+        }
+        // live window is [2, 3, 4, 5, 6]; truncate to the 3 head-most elements
+        // leaves a non-full, wrapped buffer (live data spans physical indices
+        // 1..=3, with 4 and 0 dropped). `Index` must key off `cap`, not `len`, or
+        // it reads a dropped slot here.
+        buffer.truncate(3_usize);
+
+        assert_eq!(buffer[0], 2_i32);
+        assert_eq!(buffer[1], 3_i32);
+        assert_eq!(buffer[2], 4_i32);
+    }
+
+    #[test]
+    fn test_truncate_panicking_drop_does_not_double_free() {
+        use std::panic::{self, AssertUnwindSafe};
+
+        let drops = Rc::new(RefCell::new(Vec::new()));
+
+        let mut buffer: CircularBuffer<PanicOnDrop> = CircularBuffer::new(5_usize);
+        for i in 1..=3_i32 {
+            buffer.insert(PanicOnDrop(i, drops.clone()));
+        }
+        // truncate(1) drops elements 2 and 3; element 2's `Drop` panics, which
+        // aborts the drop loop before element 3 is reached (so element 3 leaks,
+        // same as a panicking `Vec::truncate`). `len`/`tail` are already shrunk to
+        // exclude both slots by the time the panic unwinds, so the buffer's own
+        // `Drop::drop` below only re-drops the still-live element 1, never 2 or 3.
+        let result = panic::catch_unwind(AssertUnwindSafe(|| buffer.truncate(1)));
+        assert!(result.is_err());
+        drop(buffer);
+
+        let mut seen = drops.borrow().clone();
+        seen.sort_unstable();
+        // Element 2 appears exactly once (a double-free would show it twice); element
+        // 1 is dropped normally by the buffer; element 3 leaks rather than double-drops.
+        assert_eq!(seen, vec![1_i32, 2_i32]);
+    }
+
+    #[test]
+    fn test_clear_panicking_drop_does_not_double_free() {
+        use std::panic::{self, AssertUnwindSafe};
+
+        let drops = Rc::new(RefCell::new(Vec::new()));
+
+        let mut buffer: CircularBuffer<PanicOnDrop> = CircularBuffer::new(5_usize);
+        for i in 1..=3_i32 {
+            buffer.insert(PanicOnDrop(i, drops.clone()));
+        }
+        // `clear` resets `len`/`tail` to the empty state before dropping anything, so
+        // even though element 2's `Drop` panics (aborting the drop loop before
+        // element 3 is reached, which leaks it), the buffer's own `Drop::drop`
+        // below has nothing left to re-drop.
+        let result = panic::catch_unwind(AssertUnwindSafe(|| buffer.clear()));
+        assert!(result.is_err());
+        drop(buffer);
+
+        let mut seen = drops.borrow().clone();
+        seen.sort_unstable();
+        // Element 1 is dropped normally, element 2 appears exactly once (a
+        // double-free would show it twice), and element 3 leaks rather than
+        // double-drops.
+        assert_eq!(seen, vec![1_i32, 2_i32]);
+    }
+
+    #[test]
+    fn test_drain_middle() {
+        let mut buffer: CircularBuffer<i32> = CircularBuffer::new(5_usize);
+        for i in 1..=8_i32 {
+            buffer.insert(i);
+        }
+        // live window is [4, 5, 6, 7, 8]
+        let drained: Vec<i32> = buffer.drain(1..3).collect();
+        assert_eq!(drained, vec![5_i32, 6_i32]);
+
+        let remaining: Vec<i32> = buffer.iter().copied().collect();
+        assert_eq!(remaining, vec![4_i32, 7_i32, 8_i32]);
+
+        // the buffer should keep working correctly afterward
+        buffer.insert(9_i32);
+        let remaining: Vec<i32> = buffer.iter().copied().collect();
+        assert_eq!(remaining, vec![4_i32, 7_i32, 8_i32, 9_i32]);
+    }
+
+    #[test]
+    fn test_drain_rev() {
+        let mut buffer: CircularBuffer<i32> = CircularBuffer::new(5_usize);
+        for i in 1..=5_i32 {
+            buffer.insert(i);
+        }
+        let drained: Vec<i32> = buffer.drain(1..4).rev().collect();
+        assert_eq!(drained, vec![4_i32, 3_i32, 2_i32]);
+
+        let remaining: Vec<i32> = buffer.iter().copied().collect();
+        assert_eq!(remaining, vec![1_i32, 5_i32]);
+    }
+
+    #[test]
+    fn test_drain_empty_range_is_noop() {
+        let mut buffer: CircularBuffer<i32> = CircularBuffer::new(5_usize);
+        for i in 1..=5_i32 {
+            buffer.insert(i);
+        }
+        let drained: Vec<i32> = buffer.drain(2..2).collect();
+        assert!(drained.is_empty());
+        assert_eq!(buffer.len(), 5_usize);
+        assert_eq!(
+            buffer.iter().copied().collect::<Vec<_>>(),
+            vec![1, 2, 3, 4, 5]
+        );
+    }
+
+    #[test]
+    fn test_drain_dropped_early_still_removes_range() {
+        let mut buffer: CircularBuffer<i32> = CircularBuffer::new(5_usize);
+        for i in 1..=5_i32 {
+            buffer.insert(i);
+        }
+        {
+            let mut drain = buffer.drain(1..4);
+            assert_eq!(drain.next(), Some(2_i32));
+            // dropping the rest of the iterator still removes indices 1..4
+        }
+
+        let remaining: Vec<i32> = buffer.iter().copied().collect();
+        assert_eq!(remaining, vec![1_i32, 5_i32]);
+    }
+
+    #[test]
+    fn test_drain_drops_unyielded_elements() {
+        let drops = Rc::new(RefCell::new(Vec::new()));
+
+        let mut buffer: CircularBuffer<Tracker> = CircularBuffer::new(5_usize);
+        for i in 1..=5_i32 {
+            buffer.insert(Tracker(i, drops.clone()));
+        }
+        {
+            let mut drain = buffer.drain(1..4);
+            let _ = drain.next();
+            // 2 is yielded to the caller; dropping it here drops Tracker(2).
+        }
+        let mut seen = drops.borrow().clone();
+        seen.sort_unstable();
+        // 2 (yielded and dropped by the caller), 3 and 4 (dropped by Drain::drop)
+        assert_eq!(seen, vec![2_i32, 3_i32, 4_i32]);
+    }
+
+    #[test]
+    fn test_forgotten_drain_does_not_double_free() {
+        use std::mem;
+
+        let drops = Rc::new(RefCell::new(Vec::new()));
+
+        let mut buffer: CircularBuffer<Tracker> = CircularBuffer::new(5_usize);
+        for i in 1..=5_i32 {
+            buffer.insert(Tracker(i, drops.clone()));
+        }
+        {
+            let mut drain = buffer.drain(1..4);
+            let yielded = drain.next().unwrap();
+            // Forgetting the `Drain` (instead of letting it run its `Drop`) must
+            // not leave the buffer believing the moved-out/undropped slots are
+            // still live; worst case is a leak of the un-restored tail, never a
+            // double-drop when the buffer itself is later dropped.
+            mem::forget(drain);
+            drop(yielded);
+        }
+        drop(buffer);
+
+        let mut seen = drops.borrow().clone();
+        seen.sort_unstable();
+        // 1 is untouched by the drain and still dropped normally by the buffer.
+        // 2 is dropped once, by the caller, as the yielded element. 3, 4 and 5
+        // were never restored to the buffer's bookkeeping by the forgotten
+        // `Drain`, so they leak rather than being double-dropped.
+        assert_eq!(seen, vec![1_i32, 2_i32]);
+    }
+
+    #[test]
+    fn test_drop_runs_for_live_elements() {
+        let drops = Rc::new(RefCell::new(Vec::new()));
+
+        {
+            let mut buffer: CircularBuffer<Tracker> = CircularBuffer::new(3_usize);
+            buffer.insert(Tracker(1, drops.clone()));
+            buffer.insert(Tracker(2, drops.clone()));
+            buffer.insert(Tracker(3, drops.clone()));
+            buffer.insert(Tracker(4, drops.clone()));
+            // 4 evicted 1 on overwrite
+            assert_eq!(*drops.borrow(), vec![1_i32]);
+        }
+        // dropping the buffer drops the remaining live elements: 2, 3, 4
+        let mut remaining = drops.borrow().clone();
+        remaining.sort_unstable();
+        assert_eq!(remaining, vec![1_i32, 2_i32, 3_i32, 4_i32]);
+    }
+
+    #[test]
+    fn test_from_iterator() {
+        let buffer: CircularBuffer<i32> = (1..=5_i32).collect();
+        assert_eq!(buffer.len(), 5_usize);
+        assert_eq!(
+            buffer.iter().copied().collect::<Vec<_>>(),
+            vec![1, 2, 3, 4, 5]
+        );
+    }
+
+    #[test]
+    fn test_extend_evicts_oldest() {
+        let mut buffer: CircularBuffer<i32> = CircularBuffer::new(3_usize);
+        buffer.insert(1_i32);
+        buffer.extend(vec![2_i32, 3_i32, 4_i32, 5_i32]);
+        assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), vec![3, 4, 5]);
+    }
+
+    #[test]
+    fn test_debug_shows_logical_order() {
+        let mut buffer: CircularBuffer<i32> = CircularBuffer::new(3_usize);
+        buffer.insert(1_i32);
+        buffer.insert(2_i32);
+        buffer.insert(3_i32);
+        buffer.insert(4_i32);
+        assert_eq!(format!("{buffer:?}"), "[2, 3, 4]");
+    }
+
+    #[test]
+    fn test_partial_eq_ignores_internal_offset() {
+        let mut a: CircularBuffer<i32> = CircularBuffer::new(3_usize);
+        a.insert(1_i32);
+        a.insert(2_i32);
+        a.insert(3_i32);
+        a.insert(4_i32);
+        // a's internal tail has wrapped; logical contents are [2, 3, 4].
+
+        let b: CircularBuffer<i32> = vec![2_i32, 3_i32, 4_i32].into_iter().collect();
+        assert_eq!(a, b);
+
+        let c: CircularBuffer<i32> = vec![2_i32, 3_i32, 5_i32].into_iter().collect();
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn test_hash_consistent_with_eq() {
+        use std::collections::hash_map::DefaultHasher;
+
+        fn hash_of(buffer: &CircularBuffer<i32>) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            buffer.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let mut a: CircularBuffer<i32> = CircularBuffer::new(3_usize);
+        a.insert(1_i32);
+        a.insert(2_i32);
+        a.insert(3_i32);
+        a.insert(4_i32);
+
+        let b: CircularBuffer<i32> = vec![2_i32, 3_i32, 4_i32].into_iter().collect();
+        assert_eq!(hash_of(&a), hash_of(&b));
+    }
+
     #[test]
     fn basic_queue_test() {
         let mut queue: StaticSizeQueue<i32> = StaticSizeQueue::new(10_usize);
@@ -399,4 +1692,304 @@ mod tests {
         let item = queue.pop();
         assert_eq!(item.unwrap(), 13_i32);
     }
+
+    #[test]
+    fn test_queue_clear() {
+        let mut queue: StaticSizeQueue<i32> = StaticSizeQueue::new(4_usize);
+        queue.push(1_i32).unwrap();
+        queue.push(2_i32).unwrap();
+        queue.clear();
+        assert!(queue.is_empty());
+        assert_eq!(queue.size(), 0_usize);
+
+        queue.push(3_i32).unwrap();
+        assert_eq!(queue.pop().unwrap(), 3_i32);
+    }
+
+    #[test]
+    fn test_queue_truncate_wrapped() {
+        let mut queue: StaticSizeQueue<i32> = StaticSizeQueue::new(4_usize);
+        queue.push(1_i32).unwrap();
+        queue.push(2_i32).unwrap();
+        queue.push(3_i32).unwrap();
+        queue.push(4_i32).unwrap();
+        let _ = queue.pop().unwrap();
+        let _ = queue.pop().unwrap();
+        queue.push(5_i32).unwrap();
+        queue.push(6_i32).unwrap();
+        // logical order is now [3, 4, 5, 6], physically wrapped.
+
+        queue.truncate(2_usize);
+        assert_eq!(queue.size(), 2_usize);
+        assert_eq!(queue.pop().unwrap(), 3_i32);
+        assert_eq!(queue.pop().unwrap(), 4_i32);
+        assert!(queue.is_empty());
+
+        queue.push(7_i32).unwrap();
+        assert_eq!(queue.pop().unwrap(), 7_i32);
+    }
+
+    #[test]
+    fn test_queue_truncate_noop_when_new_len_too_large() {
+        let mut queue: StaticSizeQueue<i32> = StaticSizeQueue::new(4_usize);
+        queue.push(1_i32).unwrap();
+        queue.push(2_i32).unwrap();
+        queue.truncate(5_usize);
+        assert_eq!(queue.size(), 2_usize);
+    }
+
+    #[test]
+    fn test_queue_truncate_panicking_drop_does_not_double_free() {
+        use std::panic::{self, AssertUnwindSafe};
+
+        let drops = Rc::new(RefCell::new(Vec::new()));
+
+        let mut queue: StaticSizeQueue<PanicOnDrop> = StaticSizeQueue::new(4_usize);
+        for i in 1..=3_i32 {
+            queue.push(PanicOnDrop(i, drops.clone())).unwrap();
+        }
+        // truncate(1) drops elements 2 and 3; element 2's `Drop` panics, which
+        // aborts the drop loop before element 3 is reached (so element 3 leaks,
+        // same as a panicking `Vec::truncate`). `size`/`back` are already shrunk to
+        // exclude both slots by the time the panic unwinds, so the queue's own
+        // `Drop::drop` below only re-drops the still-live element 1, never 2 or 3.
+        let result = panic::catch_unwind(AssertUnwindSafe(|| queue.truncate(1)));
+        assert!(result.is_err());
+        drop(queue);
+
+        let mut seen = drops.borrow().clone();
+        seen.sort_unstable();
+        // Element 2 appears exactly once (a double-free would show it twice); element
+        // 1 is dropped normally by the queue; element 3 leaks rather than double-drops.
+        assert_eq!(seen, vec![1_i32, 2_i32]);
+    }
+
+    #[test]
+    fn test_queue_clear_panicking_drop_does_not_double_free() {
+        use std::panic::{self, AssertUnwindSafe};
+
+        let drops = Rc::new(RefCell::new(Vec::new()));
+
+        let mut queue: StaticSizeQueue<PanicOnDrop> = StaticSizeQueue::new(4_usize);
+        for i in 1..=3_i32 {
+            queue.push(PanicOnDrop(i, drops.clone())).unwrap();
+        }
+        // `clear` resets `size`/`front`/`back` to the empty state before dropping
+        // anything, so even though element 2's `Drop` panics (aborting the drop
+        // loop before element 3 is reached, which leaks it), the queue's own
+        // `Drop::drop` below has nothing left to re-drop.
+        let result = panic::catch_unwind(AssertUnwindSafe(|| queue.clear()));
+        assert!(result.is_err());
+        drop(queue);
+
+        let mut seen = drops.borrow().clone();
+        seen.sort_unstable();
+        // Element 1 is dropped normally, element 2 appears exactly once (a
+        // double-free would show it twice), and element 3 leaks rather than
+        // double-drops.
+        assert_eq!(seen, vec![1_i32, 2_i32]);
+    }
+
+    #[test]
+    fn test_queue_drain_middle() {
+        let mut queue: StaticSizeQueue<i32> = StaticSizeQueue::new(5_usize);
+        queue.push(1_i32).unwrap();
+        queue.push(2_i32).unwrap();
+        queue.push(3_i32).unwrap();
+        queue.push(4_i32).unwrap();
+        queue.push(5_i32).unwrap();
+
+        let drained: Vec<i32> = queue.drain(1..3).collect();
+        assert_eq!(drained, vec![2_i32, 3_i32]);
+        assert_eq!(queue.size(), 3_usize);
+        assert_eq!(queue.pop().unwrap(), 1_i32);
+        assert_eq!(queue.pop().unwrap(), 4_i32);
+        assert_eq!(queue.pop().unwrap(), 5_i32);
+    }
+
+    #[test]
+    fn test_queue_drain_rev() {
+        let mut queue: StaticSizeQueue<i32> = StaticSizeQueue::new(4_usize);
+        queue.push(1_i32).unwrap();
+        queue.push(2_i32).unwrap();
+        queue.push(3_i32).unwrap();
+
+        let drained: Vec<i32> = queue.drain(..).rev().collect();
+        assert_eq!(drained, vec![3_i32, 2_i32, 1_i32]);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_queue_drain_empty_range_is_noop() {
+        let mut queue: StaticSizeQueue<i32> = StaticSizeQueue::new(5_usize);
+        queue.push(1_i32).unwrap();
+        queue.push(2_i32).unwrap();
+        queue.push(3_i32).unwrap();
+
+        let drained: Vec<i32> = queue.drain(1..1).collect();
+        assert!(drained.is_empty());
+        assert_eq!(queue.size(), 3_usize);
+        assert_eq!(
+            queue.logical_iter().copied().collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn test_queue_drain_dropped_early_still_removes_range() {
+        let mut queue: StaticSizeQueue<i32> = StaticSizeQueue::new(5_usize);
+        queue.push(1_i32).unwrap();
+        queue.push(2_i32).unwrap();
+        queue.push(3_i32).unwrap();
+        queue.push(4_i32).unwrap();
+        queue.push(5_i32).unwrap();
+
+        {
+            let mut drain = queue.drain(1..4);
+            assert_eq!(drain.next().unwrap(), 2_i32);
+            // drop the rest of the drain without iterating it fully
+        }
+
+        assert_eq!(queue.size(), 2_usize);
+        assert_eq!(queue.pop().unwrap(), 1_i32);
+        assert_eq!(queue.pop().unwrap(), 5_i32);
+    }
+
+    #[test]
+    fn test_queue_drain_drops_unyielded_elements() {
+        let drops = Rc::new(RefCell::new(Vec::new()));
+
+        let mut queue: StaticSizeQueue<Tracker> = StaticSizeQueue::new(3_usize);
+        queue.push(Tracker(1, drops.clone())).unwrap();
+        queue.push(Tracker(2, drops.clone())).unwrap();
+        queue.push(Tracker(3, drops.clone())).unwrap();
+
+        {
+            let mut drain = queue.drain(0..3);
+            let first = drain.next().unwrap();
+            assert_eq!(first.0, 1_i32);
+            // remaining two elements dropped when `drain` goes out of scope here.
+        }
+
+        let mut dropped = drops.borrow().clone();
+        dropped.sort_unstable();
+        assert_eq!(dropped, vec![1_i32, 2_i32, 3_i32]);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_forgotten_queue_drain_does_not_double_free() {
+        use std::mem;
+
+        let drops = Rc::new(RefCell::new(Vec::new()));
+
+        let mut queue: StaticSizeQueue<Tracker> = StaticSizeQueue::new(5_usize);
+        for i in 1..=5_i32 {
+            queue.push(Tracker(i, drops.clone())).unwrap();
+        }
+        {
+            let mut drain = queue.drain(1..4);
+            let yielded = drain.next().unwrap();
+            // Forgetting the `QueueDrain` must not leave the queue believing the
+            // moved-out/undropped slots are still live; worst case is a leak of
+            // the un-restored tail, never a double-drop when the queue itself is
+            // later dropped.
+            mem::forget(drain);
+            drop(yielded);
+        }
+        drop(queue);
+
+        let mut seen = drops.borrow().clone();
+        seen.sort_unstable();
+        // 1 is untouched by the drain and still dropped normally by the queue.
+        // 2 is dropped once, by the caller, as the yielded element. 3, 4 and 5
+        // were never restored to the queue's bookkeeping by the forgotten
+        // `QueueDrain`, so they leak rather than being double-dropped.
+        assert_eq!(seen, vec![1_i32, 2_i32]);
+    }
+
+    #[test]
+    fn test_queue_drop_runs_for_live_elements() {
+        let drops = Rc::new(RefCell::new(Vec::new()));
+
+        {
+            let mut queue: StaticSizeQueue<Tracker> = StaticSizeQueue::new(3_usize);
+            queue.push(Tracker(1, drops.clone())).unwrap();
+            queue.push(Tracker(2, drops.clone())).unwrap();
+            let _ = queue.pop().unwrap();
+            // 1 is dropped when the caller drops the popped item.
+            assert_eq!(*drops.borrow(), vec![1_i32]);
+        }
+        // dropping the queue drops the remaining live element: 2
+        let mut remaining = drops.borrow().clone();
+        remaining.sort_unstable();
+        assert_eq!(remaining, vec![1_i32, 2_i32]);
+    }
+
+    #[test]
+    fn test_queue_from_iterator() {
+        let queue: StaticSizeQueue<i32> = (1..=5_i32).collect();
+        assert_eq!(queue.size(), 5_usize);
+        assert_eq!(
+            queue.logical_iter().copied().collect::<Vec<_>>(),
+            vec![1, 2, 3, 4, 5]
+        );
+    }
+
+    #[test]
+    fn test_queue_extend_stops_when_full() {
+        let mut queue: StaticSizeQueue<i32> = StaticSizeQueue::new(3_usize);
+        queue.push(1_i32).unwrap();
+        queue.extend(vec![2_i32, 3_i32, 4_i32, 5_i32]);
+        assert_eq!(
+            queue.logical_iter().copied().collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn test_queue_debug_shows_logical_order() {
+        let mut queue: StaticSizeQueue<i32> = StaticSizeQueue::new(4_usize);
+        queue.push(1_i32).unwrap();
+        queue.push(2_i32).unwrap();
+        let _ = queue.pop().unwrap();
+        queue.push(3_i32).unwrap();
+        assert_eq!(format!("{queue:?}"), "[2, 3]");
+    }
+
+    #[test]
+    fn test_queue_partial_eq_ignores_internal_offset() {
+        let mut a: StaticSizeQueue<i32> = StaticSizeQueue::new(3_usize);
+        a.push(1_i32).unwrap();
+        a.push(2_i32).unwrap();
+        let _ = a.pop().unwrap();
+        a.push(3_i32).unwrap();
+        // a's logical contents are [2, 3] but front no longer sits at index 0.
+
+        let b: StaticSizeQueue<i32> = vec![2_i32, 3_i32].into_iter().collect();
+        assert_eq!(a, b);
+
+        let c: StaticSizeQueue<i32> = vec![2_i32, 4_i32].into_iter().collect();
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn test_queue_hash_consistent_with_eq() {
+        use std::collections::hash_map::DefaultHasher;
+
+        fn hash_of(queue: &StaticSizeQueue<i32>) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            queue.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let mut a: StaticSizeQueue<i32> = StaticSizeQueue::new(3_usize);
+        a.push(1_i32).unwrap();
+        a.push(2_i32).unwrap();
+        let _ = a.pop().unwrap();
+        a.push(3_i32).unwrap();
+
+        let b: StaticSizeQueue<i32> = vec![2_i32, 3_i32].into_iter().collect();
+        assert_eq!(hash_of(&a), hash_of(&b));
+    }
 }
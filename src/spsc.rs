@@ -0,0 +1,205 @@
+//! A lock-free single-producer/single-consumer ring buffer.
+//!
+//! [`channel`] hands back a [`Producer`] and a [`Consumer`] sharing one preallocated
+//! ring. One thread may push through the `Producer` while another pops through the
+//! `Consumer`, with no mutex: `front`/`back` are `AtomicUsize`, and one slot is
+//! sacrificed (`back + 1 == front` means full) so empty and full are distinguishable
+//! without a separate atomically-shared size counter.
+//!
+//! This is an intentionally standalone type, not a view onto [`crate::StaticSizeQueue`]:
+//! that type's `front`/`back`/`size` are plain `usize`s mutated through `&mut self`,
+//! which can't be handed out to two threads at once without becoming atomics, and
+//! making that change would ripple through every other `StaticSizeQueue` method
+//! (`drain`, `truncate`, `clear`, the collection trait impls, ...) for the sake of
+//! this one lock-free use case. `Shared<T>` instead re-derives the same
+//! preallocated-ring, one-slot-sacrificed design directly against atomic cursors.
+
+use std::cell::UnsafeCell;
+use std::mem::MaybeUninit;
+use std::ptr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crate::QueueError;
+
+struct Shared<T> {
+    buf: Box<[UnsafeCell<MaybeUninit<T>>]>,
+    cap: usize,
+    front: AtomicUsize,
+    back: AtomicUsize,
+}
+
+// SAFETY: `Producer` and `Consumer` only ever touch disjoint slots of `buf` at any
+// given time (the producer writes `back`, the consumer reads `front`, and the
+// full/empty checks ensure they never overlap), so `Shared<T>` is safe to share
+// across threads whenever `T` is `Send`.
+unsafe impl<T: Send> Sync for Shared<T> {}
+
+impl<T> Drop for Shared<T> {
+    fn drop(&mut self) {
+        let front = *self.front.get_mut();
+        let back = *self.back.get_mut();
+        let mut i = front;
+        while i != back {
+            unsafe {
+                ptr::drop_in_place((*self.buf[i].get()).as_mut_ptr());
+            }
+            i = (i + 1) % self.cap;
+        }
+    }
+}
+
+/// The producing half of an SPSC ring, created by [`channel`]. Owns `push`.
+pub struct Producer<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// The consuming half of an SPSC ring, created by [`channel`]. Owns `pop`.
+pub struct Consumer<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// Creates a wait-free bounded SPSC ring of capacity `cap`, split into a
+/// [`Producer`] and a [`Consumer`] handle.
+pub fn channel<T>(cap: usize) -> (Producer<T>, Consumer<T>) {
+    assert!(cap > 0_usize, "Attempt to initialize 0 size spsc queue");
+    // One slot is sacrificed to disambiguate empty from full.
+    let alloc_cap = cap + 1;
+    let buf = (0..alloc_cap)
+        .map(|_| UnsafeCell::new(MaybeUninit::uninit()))
+        .collect();
+    let shared = Arc::new(Shared {
+        buf,
+        cap: alloc_cap,
+        front: AtomicUsize::new(0_usize),
+        back: AtomicUsize::new(0_usize),
+    });
+
+    (
+        Producer {
+            shared: shared.clone(),
+        },
+        Consumer { shared },
+    )
+}
+
+impl<T> Producer<T> {
+    /// Pushes an item onto the ring. Returns `QueueError::QueueFull` (dropping
+    /// `item`) if the consumer hasn't kept up.
+    pub fn push(&mut self, item: T) -> Result<(), QueueError> {
+        let back = self.shared.back.load(Ordering::Relaxed);
+        let next = (back + 1) % self.shared.cap;
+        if next == self.shared.front.load(Ordering::Acquire) {
+            return Err(QueueError::QueueFull);
+        }
+
+        unsafe {
+            (*self.shared.buf[back].get()).write(item);
+        }
+        // Release so the consumer's acquire load of `back` happens-after this write.
+        self.shared.back.store(next, Ordering::Release);
+        Ok(())
+    }
+}
+
+impl<T> Consumer<T> {
+    /// Pops the oldest item off the ring. Returns `QueueError::QueueEmpty` if the
+    /// producer hasn't pushed anything new.
+    pub fn pop(&mut self) -> Result<T, QueueError> {
+        let front = self.shared.front.load(Ordering::Relaxed);
+        if front == self.shared.back.load(Ordering::Acquire) {
+            return Err(QueueError::QueueEmpty);
+        }
+
+        // SAFETY: `front != back` means the producer has published a write to this
+        // slot (observed via the acquire load of `back` above), and the consumer is
+        // the only side that ever reads the slot at `front` or advances `front`.
+        let item = unsafe { (*self.shared.buf[front].get()).assume_init_read() };
+        let next = (front + 1) % self.shared.cap;
+        // Release so the producer's acquire load of `front` happens-after this read.
+        self.shared.front.store(next, Ordering::Release);
+        Ok(item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_spsc_push_pop_single_thread() {
+        let (mut producer, mut consumer) = channel::<i32>(3_usize);
+
+        assert!(consumer.pop().is_err());
+
+        producer.push(1_i32).unwrap();
+        producer.push(2_i32).unwrap();
+        producer.push(3_i32).unwrap();
+        assert!(producer.push(4_i32).is_err());
+
+        assert_eq!(consumer.pop().unwrap(), 1_i32);
+        assert_eq!(consumer.pop().unwrap(), 2_i32);
+        assert_eq!(consumer.pop().unwrap(), 3_i32);
+        assert!(consumer.pop().is_err());
+
+        producer.push(5_i32).unwrap();
+        assert_eq!(consumer.pop().unwrap(), 5_i32);
+    }
+
+    #[test]
+    fn test_spsc_across_threads() {
+        let (mut producer, mut consumer) = channel::<i32>(4_usize);
+
+        let producer_handle = thread::spawn(move || {
+            for i in 0..1000_i32 {
+                loop {
+                    if producer.push(i).is_ok() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        let consumer_handle = thread::spawn(move || {
+            let mut received = Vec::with_capacity(1000_usize);
+            while received.len() < 1000_usize {
+                if let Ok(item) = consumer.pop() {
+                    received.push(item);
+                }
+            }
+            received
+        });
+
+        producer_handle.join().unwrap();
+        let received = consumer_handle.join().unwrap();
+        assert_eq!(received, (0..1000_i32).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_spsc_drops_unreceived_items() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let drops = Rc::new(RefCell::new(Vec::new()));
+
+        struct Tracker(i32, Rc<RefCell<Vec<i32>>>);
+        impl Drop for Tracker {
+            fn drop(&mut self) {
+                self.1.borrow_mut().push(self.0);
+            }
+        }
+
+        {
+            let (mut producer, consumer) = channel::<Tracker>(3_usize);
+            producer.push(Tracker(1_i32, drops.clone())).unwrap();
+            producer.push(Tracker(2_i32, drops.clone())).unwrap();
+            drop(consumer);
+            drop(producer);
+        }
+
+        let mut remaining = drops.borrow().clone();
+        remaining.sort_unstable();
+        assert_eq!(remaining, vec![1_i32, 2_i32]);
+    }
+}